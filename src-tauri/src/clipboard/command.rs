@@ -0,0 +1,238 @@
+use super::{ClipboardKind, ClipboardProvider};
+use std::borrow::Cow;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+type Invocation = (&'static str, &'static [&'static str]);
+
+/// A provider backed by a pair of external commands, one to read the
+/// clipboard and one to write it. Covers `pbcopy`/`pbpaste`, `wl-copy`/
+/// `wl-paste`, `xclip`, `xsel`, and `termux-clipboard-get`/`-set`. Tools
+/// that also expose the X11/Wayland primary selection carry a second pair;
+/// `pasteboard`, `termux`, and `tmux` have no such concept and leave it
+/// unset.
+pub struct CommandProvider {
+    name: &'static str,
+    get: Invocation,
+    set: Invocation,
+    primary_get: Option<Invocation>,
+    primary_set: Option<Invocation>,
+}
+
+impl CommandProvider {
+    pub fn pasteboard() -> Self {
+        Self {
+            name: "pasteboard",
+            get: ("pbpaste", &[]),
+            set: ("pbcopy", &[]),
+            primary_get: None,
+            primary_set: None,
+        }
+    }
+
+    pub fn wayland() -> Self {
+        Self {
+            name: "wayland",
+            get: ("wl-paste", &["--no-newline"]),
+            set: ("wl-copy", &[]),
+            primary_get: Some(("wl-paste", &["--primary", "--no-newline"])),
+            primary_set: Some(("wl-copy", &["--primary"])),
+        }
+    }
+
+    pub fn xclip() -> Self {
+        Self {
+            name: "x-clip",
+            get: ("xclip", &["-o", "-selection", "clipboard"]),
+            set: ("xclip", &["-selection", "clipboard"]),
+            primary_get: Some(("xclip", &["-o", "-selection", "primary"])),
+            primary_set: Some(("xclip", &["-selection", "primary"])),
+        }
+    }
+
+    pub fn xsel() -> Self {
+        Self {
+            name: "x-sel",
+            get: ("xsel", &["--clipboard", "--output"]),
+            set: ("xsel", &["--clipboard", "--input"]),
+            primary_get: Some(("xsel", &["--primary", "--output"])),
+            primary_set: Some(("xsel", &["--primary", "--input"])),
+        }
+    }
+
+    pub fn termux() -> Self {
+        Self {
+            name: "termux",
+            get: ("termux-clipboard-get", &[]),
+            set: ("termux-clipboard-set", &[]),
+            primary_get: None,
+            primary_set: None,
+        }
+    }
+
+    pub fn tmux() -> Self {
+        Self {
+            name: "tmux",
+            get: ("tmux", &["save-buffer", "-"]),
+            set: ("tmux", &["load-buffer", "-"]),
+            primary_get: None,
+            primary_set: None,
+        }
+    }
+
+    fn invocation_for(&self, kind: ClipboardKind) -> Result<(Invocation, Invocation), String> {
+        match kind {
+            ClipboardKind::Clipboard => Ok((self.get, self.set)),
+            ClipboardKind::Primary => {
+                let get = self.primary_get.ok_or_else(|| {
+                    format!("{} has no primary selection", self.name)
+                })?;
+                let set = self.primary_set.ok_or_else(|| {
+                    format!("{} has no primary selection", self.name)
+                })?;
+                Ok((get, set))
+            }
+        }
+    }
+}
+
+impl ClipboardProvider for CommandProvider {
+    fn name(&self) -> Cow<str> {
+        Cow::Borrowed(self.name)
+    }
+
+    fn get_contents(&self, kind: ClipboardKind) -> Result<String, String> {
+        let ((cmd, args), _) = self.invocation_for(kind)?;
+        let output = Command::new(cmd)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to run {}: {}", cmd, e))?;
+
+        if !output.status.success() {
+            return Err(format!("{} exited with an error", cmd));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn set_contents(&self, content: String, kind: ClipboardKind) -> Result<(), String> {
+        let (_, (cmd, args)) = self.invocation_for(kind)?;
+        let mut child = Command::new(cmd)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run {}: {}", cmd, e))?;
+
+        if let Some(stdin) = child.stdin.as_mut() {
+            stdin
+                .write_all(content.as_bytes())
+                .map_err(|e| format!("Failed to write to {}: {}", cmd, e))?;
+        }
+
+        child
+            .wait()
+            .map_err(|e| format!("Failed to wait for {}: {}", cmd, e))?;
+
+        Ok(())
+    }
+
+    fn supports(&self, kind: ClipboardKind) -> bool {
+        match kind {
+            ClipboardKind::Clipboard => true,
+            ClipboardKind::Primary => self.primary_get.is_some() && self.primary_set.is_some(),
+        }
+    }
+}
+
+/// Windows backend, kept separate from `CommandProvider` since it shells out
+/// through PowerShell rather than a single fixed binary. Windows has no
+/// primary selection, so that kind is always rejected.
+#[cfg(target_os = "windows")]
+pub struct PowerShellProvider;
+
+#[cfg(target_os = "windows")]
+impl ClipboardProvider for PowerShellProvider {
+    fn name(&self) -> Cow<str> {
+        Cow::Borrowed("powershell")
+    }
+
+    fn get_contents(&self, kind: ClipboardKind) -> Result<String, String> {
+        if kind == ClipboardKind::Primary {
+            return Err("Windows has no primary selection".to_string());
+        }
+
+        let output = Command::new("powershell")
+            .args(&["-command", "Get-Clipboard -Format Text"])
+            .output()
+            .map_err(|e| format!("Failed to get clipboard: {}", e))?;
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn set_contents(&self, content: String, kind: ClipboardKind) -> Result<(), String> {
+        if kind == ClipboardKind::Primary {
+            return Err("Windows has no primary selection".to_string());
+        }
+
+        let output = Command::new("powershell")
+            .args(&["-command", &format!("Set-Clipboard -Value '{}'", content)])
+            .output()
+            .map_err(|e| format!("Failed to set clipboard: {}", e))?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err("Failed to set clipboard content".to_string())
+        }
+    }
+}
+
+/// Fallback used when no known clipboard tool could be found on `$PATH`.
+pub struct UnsupportedProvider;
+
+impl ClipboardProvider for UnsupportedProvider {
+    fn name(&self) -> Cow<str> {
+        Cow::Borrowed("unsupported")
+    }
+
+    fn get_contents(&self, _kind: ClipboardKind) -> Result<String, String> {
+        Err("Unsupported platform: no clipboard tool found".to_string())
+    }
+
+    fn set_contents(&self, _content: String, _kind: ClipboardKind) -> Result<(), String> {
+        Err("Unsupported platform: no clipboard tool found".to_string())
+    }
+
+    fn supports(&self, _kind: ClipboardKind) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_clipboard_backends_reject_primary() {
+        for provider in [CommandProvider::pasteboard(), CommandProvider::termux(), CommandProvider::tmux()] {
+            assert!(provider.invocation_for(ClipboardKind::Primary).is_err());
+            assert!(!provider.supports(ClipboardKind::Primary));
+            assert!(provider.supports(ClipboardKind::Clipboard));
+        }
+    }
+
+    #[test]
+    fn dual_selection_backends_accept_primary() {
+        for provider in [CommandProvider::wayland(), CommandProvider::xclip(), CommandProvider::xsel()] {
+            assert!(provider.invocation_for(ClipboardKind::Primary).is_ok());
+            assert!(provider.supports(ClipboardKind::Primary));
+        }
+    }
+
+    #[test]
+    fn unsupported_provider_rejects_everything() {
+        let provider = UnsupportedProvider;
+        assert!(!provider.supports(ClipboardKind::Clipboard));
+        assert!(!provider.supports(ClipboardKind::Primary));
+    }
+}
@@ -0,0 +1,118 @@
+use super::{ClipboardKind, ClipboardProvider};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// User-supplied clipboard commands, the same shape as Helix's
+/// `clipboard-provider` setting: a `yank`/`paste` pair for the regular
+/// clipboard, plus an optional `primary-yank`/`primary-paste` pair for the
+/// X11/Wayland primary selection.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CustomProviderConfig {
+    pub yank: String,
+    #[serde(default)]
+    pub yank_args: Vec<String>,
+    pub paste: String,
+    #[serde(default)]
+    pub paste_args: Vec<String>,
+    #[serde(default)]
+    pub primary_yank: Option<String>,
+    #[serde(default)]
+    pub primary_yank_args: Vec<String>,
+    #[serde(default)]
+    pub primary_paste: Option<String>,
+    #[serde(default)]
+    pub primary_paste_args: Vec<String>,
+}
+
+/// Runs the configured `yank`/`paste` commands, piping content to stdin on
+/// copy and reading it back from stdout on paste, for users whose preferred
+/// clipboard tool isn't one of the built-in backends.
+pub struct CustomProvider {
+    config: CustomProviderConfig,
+}
+
+impl CustomProvider {
+    pub fn new(config: CustomProviderConfig) -> Self {
+        Self { config }
+    }
+
+    fn paste_command(&self, kind: ClipboardKind) -> Result<(&str, &[String]), String> {
+        match kind {
+            ClipboardKind::Clipboard => Ok((&self.config.paste, &self.config.paste_args)),
+            ClipboardKind::Primary => {
+                let cmd = self
+                    .config
+                    .primary_paste
+                    .as_deref()
+                    .ok_or_else(|| "custom provider has no primary-paste command".to_string())?;
+                Ok((cmd, &self.config.primary_paste_args))
+            }
+        }
+    }
+
+    fn yank_command(&self, kind: ClipboardKind) -> Result<(&str, &[String]), String> {
+        match kind {
+            ClipboardKind::Clipboard => Ok((&self.config.yank, &self.config.yank_args)),
+            ClipboardKind::Primary => {
+                let cmd = self
+                    .config
+                    .primary_yank
+                    .as_deref()
+                    .ok_or_else(|| "custom provider has no primary-yank command".to_string())?;
+                Ok((cmd, &self.config.primary_yank_args))
+            }
+        }
+    }
+}
+
+impl ClipboardProvider for CustomProvider {
+    fn name(&self) -> Cow<str> {
+        Cow::Borrowed("custom")
+    }
+
+    fn get_contents(&self, kind: ClipboardKind) -> Result<String, String> {
+        let (cmd, args) = self.paste_command(kind)?;
+        let output = Command::new(cmd)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to run {}: {}", cmd, e))?;
+
+        if !output.status.success() {
+            return Err(format!("{} exited with an error", cmd));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn set_contents(&self, content: String, kind: ClipboardKind) -> Result<(), String> {
+        let (cmd, args) = self.yank_command(kind)?;
+        let mut child = Command::new(cmd)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run {}: {}", cmd, e))?;
+
+        if let Some(stdin) = child.stdin.as_mut() {
+            stdin
+                .write_all(content.as_bytes())
+                .map_err(|e| format!("Failed to write to {}: {}", cmd, e))?;
+        }
+
+        child
+            .wait()
+            .map_err(|e| format!("Failed to wait for {}: {}", cmd, e))?;
+
+        Ok(())
+    }
+
+    fn supports(&self, kind: ClipboardKind) -> bool {
+        match kind {
+            ClipboardKind::Clipboard => true,
+            ClipboardKind::Primary => {
+                self.config.primary_yank.is_some() && self.config.primary_paste.is_some()
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+mod command;
+mod custom;
+mod native;
+mod osc52;
+
+pub use command::CommandProvider;
+pub use custom::{CustomProvider, CustomProviderConfig};
+pub use native::{set_image_from_png, ArboardProvider};
+pub use osc52::Osc52Provider;
+
+use crate::ClipboardItem;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Which of the two X11/Wayland selections to target. Most platforms only
+/// have one clipboard, so providers without a primary selection (macOS,
+/// Windows, Termux, tmux, arboard) reject `Primary`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardKind {
+    Clipboard,
+    Primary,
+}
+
+impl ClipboardKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClipboardKind::Clipboard => "clipboard",
+            ClipboardKind::Primary => "primary",
+        }
+    }
+}
+
+impl std::str::FromStr for ClipboardKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clipboard" => Ok(ClipboardKind::Clipboard),
+            "primary" => Ok(ClipboardKind::Primary),
+            other => Err(format!("Unknown clipboard kind: {}", other)),
+        }
+    }
+}
+
+/// A clipboard backend capable of reading and writing the system clipboard.
+///
+/// Implementations are expected to be cheap to construct; `detect_provider`
+/// picks one at startup based on the running platform and the tools it can
+/// find, the same way `ClipboardState` used to be hard-wired to a single
+/// `cfg`-gated pair of functions.
+pub trait ClipboardProvider: Send + Sync {
+    /// Human-readable identifier, e.g. "pasteboard" or "x-clip".
+    fn name(&self) -> Cow<str>;
+    fn get_contents(&self, kind: ClipboardKind) -> Result<String, String>;
+    fn set_contents(&self, content: String, kind: ClipboardKind) -> Result<(), String>;
+
+    /// Whether this backend can serve `kind` at all, so callers (the
+    /// monitor loop in particular) can skip kinds it would only ever
+    /// reject instead of polling them and logging the resulting error.
+    /// The default reports the regular clipboard only, since most backends
+    /// don't have a primary selection; `UnsupportedProvider` reports neither.
+    fn supports(&self, kind: ClipboardKind) -> bool {
+        kind == ClipboardKind::Clipboard
+    }
+}
+
+/// Probe the environment and pick the best available clipboard backend.
+///
+/// The native `arboard` backend is tried first since it reads and writes
+/// text and images in-process on every desktop platform. It fails to
+/// initialize on a display-less host; if that happens inside an SSH session
+/// (`SSH_TTY` or `SSH_CONNECTION` is set), `pbcopy`/`xclip` wouldn't reach
+/// the user's real clipboard anyway, so we switch to OSC 52 instead of
+/// falling through to the external command chain. Otherwise detection mirrors the
+/// order used by terminal editors like Helix/Neovim: on Linux prefer
+/// Wayland tooling when a compositor is running, then fall back through
+/// X11 tools, Termux, and finally tmux's own buffer.
+pub fn detect_provider(history: Arc<Mutex<VecDeque<ClipboardItem>>>) -> Box<dyn ClipboardProvider> {
+    if let Ok(provider) = ArboardProvider::new() {
+        return Box::new(provider);
+    }
+
+    if std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some() {
+        return Box::new(Osc52Provider::new(history));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        return Box::new(CommandProvider::pasteboard());
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        return Box::new(command::PowerShellProvider);
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() && command_exists("wl-copy") {
+            return Box::new(CommandProvider::wayland());
+        }
+        if command_exists("xclip") {
+            return Box::new(CommandProvider::xclip());
+        }
+        if command_exists("xsel") {
+            return Box::new(CommandProvider::xsel());
+        }
+        if command_exists("termux-clipboard-get") {
+            return Box::new(CommandProvider::termux());
+        }
+        if std::env::var_os("TMUX").is_some() && command_exists("tmux") {
+            return Box::new(CommandProvider::tmux());
+        }
+        Box::new(command::UnsupportedProvider)
+    }
+}
+
+/// Find a backend to poll the X11/Wayland primary selection alongside
+/// whatever `detect_provider` picked as the main one.
+///
+/// `detect_provider` prefers `arboard`, which has no concept of the primary
+/// selection, so on Linux/BSD desktops the monitor would otherwise never
+/// see primary-selection changes unless the user manually switches to
+/// `wayland`/`x-clip`/`x-sel`. Returns `None` everywhere else (macOS,
+/// Windows, and headless/SSH hosts have no primary selection to poll).
+pub fn detect_primary_provider() -> Option<Box<dyn ClipboardProvider>> {
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        if std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some() {
+            return None;
+        }
+        if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+            return None;
+        }
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() && command_exists("wl-copy") {
+            return Some(Box::new(CommandProvider::wayland()));
+        }
+        if command_exists("xclip") {
+            return Some(Box::new(CommandProvider::xclip()));
+        }
+        if command_exists("xsel") {
+            return Some(Box::new(CommandProvider::xsel()));
+        }
+        None
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    {
+        None
+    }
+}
+
+/// Build one of the built-in named providers for `set_clipboard_provider`.
+///
+/// `custom_config` is only consulted for the `"custom"` name; if it is
+/// `None` there, a "No custom clipboard command configured" error is returned.
+pub fn build_named_provider(
+    name: &str,
+    history: Arc<Mutex<VecDeque<ClipboardItem>>>,
+    custom_config: Option<CustomProviderConfig>,
+) -> Result<Box<dyn ClipboardProvider>, String> {
+    match name {
+        "pasteboard" => Ok(Box::new(CommandProvider::pasteboard())),
+        "wayland" => Ok(Box::new(CommandProvider::wayland())),
+        "x-clip" => Ok(Box::new(CommandProvider::xclip())),
+        "x-sel" => Ok(Box::new(CommandProvider::xsel())),
+        "tmux" => Ok(Box::new(CommandProvider::tmux())),
+        "osc52" => Ok(Box::new(Osc52Provider::new(history))),
+        "custom" => {
+            let config = custom_config
+                .ok_or_else(|| "No custom clipboard command configured".to_string())?;
+            Ok(Box::new(CustomProvider::new(config)))
+        }
+        other => Err(format!("Unknown clipboard provider: {}", other)),
+    }
+}
+
+/// Check whether `cmd` is on `$PATH`, without caring what it prints.
+fn command_exists(cmd: &str) -> bool {
+    std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("command -v {} >/dev/null 2>&1", cmd))
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn parses_known_kinds() {
+        assert_eq!(ClipboardKind::from_str("clipboard"), Ok(ClipboardKind::Clipboard));
+        assert_eq!(ClipboardKind::from_str("primary"), Ok(ClipboardKind::Primary));
+    }
+
+    #[test]
+    fn rejects_unknown_kind() {
+        assert!(ClipboardKind::from_str("selection").is_err());
+    }
+}
@@ -0,0 +1,108 @@
+use super::{ClipboardKind, ClipboardProvider};
+use arboard::{Clipboard, ImageData};
+use base64::{engine::general_purpose::STANDARD as BASE64_ENGINE, Engine as _};
+use std::borrow::Cow;
+use std::sync::Mutex;
+
+/// Native backend built on `arboard`: reads and writes the OS clipboard
+/// in-process instead of shelling out to `pbpaste`/`osascript`/PowerShell
+/// twice a second. Covers macOS, Windows, and Linux (X11 and Wayland, via
+/// arboard's `wl-clipboard-rs` support), so it is tried before falling
+/// back to the external command chain.
+pub struct ArboardProvider {
+    clipboard: Mutex<Clipboard>,
+}
+
+impl ArboardProvider {
+    pub fn new() -> Result<Self, String> {
+        let clipboard = Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?;
+        Ok(Self {
+            clipboard: Mutex::new(clipboard),
+        })
+    }
+}
+
+impl ClipboardProvider for ArboardProvider {
+    fn name(&self) -> Cow<str> {
+        Cow::Borrowed("arboard")
+    }
+
+    fn get_contents(&self, kind: ClipboardKind) -> Result<String, String> {
+        if kind == ClipboardKind::Primary {
+            return Err("arboard does not support the primary selection".to_string());
+        }
+
+        let mut clipboard = self.clipboard.lock().unwrap();
+        if let Ok(text) = clipboard.get_text() {
+            return Ok(text);
+        }
+
+        let image = clipboard
+            .get_image()
+            .map_err(|e| format!("Failed to read clipboard: {}", e))?;
+        let png = encode_png(&image)?;
+        Ok(format!("data:image/png;base64,{}", BASE64_ENGINE.encode(png)))
+    }
+
+    fn set_contents(&self, content: String, kind: ClipboardKind) -> Result<(), String> {
+        if kind == ClipboardKind::Primary {
+            return Err("arboard does not support the primary selection".to_string());
+        }
+
+        let mut clipboard = self.clipboard.lock().unwrap();
+        clipboard
+            .set_text(content)
+            .map_err(|e| format!("Failed to set clipboard: {}", e))
+    }
+}
+
+/// Re-encode the raw RGBA pixels `arboard` hands back into a PNG, the
+/// format the rest of ClipBox (history previews, `get_image_base64`)
+/// already expects.
+fn encode_png(image: &ImageData) -> Result<Vec<u8>, String> {
+    let buffer = image::ImageBuffer::<image::Rgba<u8>, _>::from_raw(
+        image.width as u32,
+        image.height as u32,
+        image.bytes.to_vec(),
+    )
+    .ok_or_else(|| "Clipboard image had an unexpected size".to_string())?;
+
+    let mut png = Vec::new();
+    image::DynamicImage::ImageRgba8(buffer)
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(png)
+}
+
+/// Long-lived handle for `set_image_from_png`, kept open for the life of the
+/// process instead of opening a fresh `Clipboard` per call. On Linux (X11
+/// and Wayland via `wl-clipboard-rs`) arboard serves selection requests from
+/// a background thread tied to the `Clipboard`'s lifetime, so a transient
+/// handle dropped right after `set_image` loses the image before anything
+/// can paste it.
+static IMAGE_CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+/// Decode PNG bytes and push them to the clipboard as an image, instead of
+/// writing them to a temp file and shelling out to `osascript`/
+/// `Set-Clipboard -Path` to pick them back up.
+pub fn set_image_from_png(png_bytes: &[u8]) -> Result<(), String> {
+    let image = image::load_from_memory(png_bytes)
+        .map_err(|e| format!("Failed to decode image: {}", e))?
+        .to_rgba8();
+    let (width, height) = image.dimensions();
+
+    let mut guard = IMAGE_CLIPBOARD.lock().unwrap();
+    if guard.is_none() {
+        *guard = Some(Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?);
+    }
+
+    guard
+        .as_mut()
+        .unwrap()
+        .set_image(ImageData {
+            width: width as usize,
+            height: height as usize,
+            bytes: Cow::Owned(image.into_raw()),
+        })
+        .map_err(|e| format!("Failed to set clipboard image: {}", e))
+}
@@ -0,0 +1,67 @@
+use super::{ClipboardKind, ClipboardProvider};
+use crate::ClipboardItem;
+use base64::{engine::general_purpose::STANDARD as BASE64_ENGINE, Engine as _};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// Clipboard backend for remote sessions: instead of talking to a local
+/// clipboard tool, it emits the OSC 52 terminal escape sequence so the
+/// user's *local* terminal (or tmux) picks the content back up over SSH.
+pub struct Osc52Provider {
+    history: Arc<Mutex<VecDeque<ClipboardItem>>>,
+    via_tmux: bool,
+}
+
+impl Osc52Provider {
+    pub fn new(history: Arc<Mutex<VecDeque<ClipboardItem>>>) -> Self {
+        Self {
+            history,
+            via_tmux: std::env::var_os("TMUX").is_some(),
+        }
+    }
+}
+
+impl ClipboardProvider for Osc52Provider {
+    fn name(&self) -> Cow<str> {
+        Cow::Borrowed("osc52")
+    }
+
+    fn get_contents(&self, _kind: ClipboardKind) -> Result<String, String> {
+        // Terminal read-back of OSC 52 is unreliable (many terminals don't
+        // answer it at all), so report our own most-recent history entry
+        // instead of querying the terminal.
+        let history = self.history.lock().unwrap();
+        history
+            .front()
+            .map(|item| item.content.clone())
+            .ok_or_else(|| "Clipboard history is empty".to_string())
+    }
+
+    fn set_contents(&self, content: String, kind: ClipboardKind) -> Result<(), String> {
+        let selection = match kind {
+            ClipboardKind::Clipboard => "c",
+            ClipboardKind::Primary => "p",
+        };
+        let encoded = BASE64_ENGINE.encode(content.as_bytes());
+        let sequence = format!("\x1b]52;{};{}\x07", selection, encoded);
+        let sequence = if self.via_tmux {
+            format!("\x1bPtmux;\x1b{}\x1b\\", sequence)
+        } else {
+            sequence
+        };
+
+        let mut stdout = std::io::stdout();
+        stdout
+            .write_all(sequence.as_bytes())
+            .and_then(|_| stdout.flush())
+            .map_err(|e| format!("Failed to write OSC 52 sequence: {}", e))
+    }
+
+    fn supports(&self, _kind: ClipboardKind) -> bool {
+        // OSC 52 addresses both selections by its "c"/"p" selection byte,
+        // so there's no platform-level restriction to report here.
+        true
+    }
+}
@@ -0,0 +1,105 @@
+use base64::{engine::general_purpose::STANDARD as BASE64_ENGINE, Engine as _};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// What a freshly captured clipboard payload turned out to be, and the hash
+/// used to dedup it against existing history entries.
+pub struct Classified {
+    pub item_type: String,
+    pub image_path: Option<String>,
+    pub content_hash: String,
+}
+
+/// Inspect `content` and work out what kind of clipboard item it is.
+///
+/// Images are detected by the `data:image/png;base64,` prefix `ArboardProvider`
+/// already produces. Their *decoded* bytes, not the base64 text, are hashed
+/// and written to a stable path under the OS temp dir keyed on that hash, so
+/// re-copying the same image reuses the same file instead of piling up
+/// duplicates.
+pub fn classify(content: &str) -> Classified {
+    if let Some(base64_data) = content.strip_prefix("data:image/png;base64,") {
+        if let Ok(bytes) = BASE64_ENGINE.decode(base64_data) {
+            let content_hash = hash_bytes(&bytes);
+            let path = std::env::temp_dir().join(format!("clipbox_{}.png", content_hash));
+            if std::fs::write(&path, &bytes).is_ok() {
+                return Classified {
+                    item_type: "image".to_string(),
+                    image_path: Some(path.to_string_lossy().to_string()),
+                    content_hash,
+                };
+            }
+        }
+    }
+
+    let item_type = if is_url(content) {
+        "url"
+    } else if is_hex_color(content) {
+        "color"
+    } else {
+        "text"
+    };
+
+    Classified {
+        item_type: item_type.to_string(),
+        image_path: None,
+        content_hash: hash_bytes(content.as_bytes()),
+    }
+}
+
+fn is_url(content: &str) -> bool {
+    let trimmed = content.trim();
+    trimmed.starts_with("http://") || trimmed.starts_with("https://")
+}
+
+fn is_hex_color(content: &str) -> bool {
+    let trimmed = content.trim();
+    let hex = match trimmed.strip_prefix('#') {
+        Some(hex) => hex,
+        None => return false,
+    };
+    matches!(hex.len(), 3 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn hash_bytes(bytes: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_urls() {
+        let classified = classify("https://example.com/path");
+        assert_eq!(classified.item_type, "url");
+        assert_eq!(classified.image_path, None);
+    }
+
+    #[test]
+    fn classifies_hex_colors() {
+        assert_eq!(classify("#fff").item_type, "color");
+        assert_eq!(classify("#112233").item_type, "color");
+        assert_eq!(classify("#11223344").item_type, "color");
+        // Not a color: wrong length, or non-hex characters.
+        assert_eq!(classify("#ff").item_type, "text");
+        assert_eq!(classify("#gggggg").item_type, "text");
+    }
+
+    #[test]
+    fn classifies_plain_text() {
+        assert_eq!(classify("hello world").item_type, "text");
+    }
+
+    #[test]
+    fn same_content_hashes_the_same() {
+        let a = classify("hello world");
+        let b = classify("hello world");
+        assert_eq!(a.content_hash, b.content_hash);
+
+        let c = classify("something else");
+        assert_ne!(a.content_hash, c.content_hash);
+    }
+}
@@ -1,9 +1,19 @@
+mod clipboard;
+mod history;
+
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter, State, Window, Manager};
 use serde::{Deserialize, Serialize};
 // 不使用clipboard-manager插件，继续使用原有的轮询实现
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_ENGINE};
+use clipboard::{
+    build_named_provider, detect_primary_provider, detect_provider, set_image_from_png,
+    ClipboardKind, ClipboardProvider, CustomProviderConfig,
+};
+use history::classify;
+use std::str::FromStr;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ClipboardItem {
     pub id: String,
@@ -11,18 +21,37 @@ pub struct ClipboardItem {
     pub timestamp: u64,
     pub item_type: String,
     pub image_path: Option<String>,
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub content_hash: String,
 }
 
 pub struct ClipboardState {
     pub history: Arc<Mutex<VecDeque<ClipboardItem>>>,
     pub last_content: Arc<Mutex<String>>,
+    pub primary_last_content: Arc<Mutex<String>>,
+    pub provider: Arc<Mutex<Arc<dyn ClipboardProvider>>>,
+    /// Backup backend used only to reach the primary selection when the
+    /// main `provider` can't (e.g. the default `arboard` backend), so the
+    /// monitor and `write_to_clipboard` still see/set it without the user
+    /// having to switch providers manually.
+    pub primary_provider: Option<Arc<dyn ClipboardProvider>>,
+    pub custom_config: Mutex<Option<CustomProviderConfig>>,
 }
 
 impl ClipboardState {
     pub fn new() -> Self {
+        let history = Arc::new(Mutex::new(VecDeque::new()));
+        let provider = Arc::from(detect_provider(history.clone()));
+        let primary_provider = detect_primary_provider().map(Arc::from);
         Self {
-            history: Arc::new(Mutex::new(VecDeque::new())),
+            history,
             last_content: Arc::new(Mutex::new(String::new())),
+            primary_last_content: Arc::new(Mutex::new(String::new())),
+            provider: Arc::new(Mutex::new(provider)),
+            primary_provider,
+            custom_config: Mutex::new(None),
         }
     }
 }
@@ -32,62 +61,110 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+/// Poll a single clipboard `kind`, and if its contents changed since `last`,
+/// record it in the history: an entry with the same content hash is moved to
+/// the front, otherwise a new item tagged with where it came from is added.
+fn poll_kind(
+    kind: ClipboardKind,
+    provider: &Arc<dyn ClipboardProvider>,
+    last: &Arc<Mutex<String>>,
+    history: &Arc<Mutex<VecDeque<ClipboardItem>>>,
+    app: &AppHandle,
+) {
+    match provider.get_contents(kind) {
+        Ok(content) => {
+            // 和旧版get_clipboard_content一样，跳过空白内容，避免产生空记录
+            if content.trim().is_empty() {
+                return;
+            }
+
+            println!("获取到剪贴板内容 ({}): {:?}", kind.as_str(), content);
+
+            let mut last = last.lock().unwrap();
+            if *last != content {
+                *last = content.clone();
+
+                let classified = classify(&content);
+                let mut hist = history.lock().unwrap();
+
+                // 如果内容之前已经出现过（按解码后的内容哈希判断），
+                // 只需把原记录移到最前面，而不是插入一条新记录
+                if let Some(pos) = hist.iter().position(|item| item.content_hash == classified.content_hash) {
+                    let mut item = hist.remove(pos).unwrap();
+                    item.timestamp = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs();
+                    println!("item 已存在，移动到最前: {:?}", item);
+                    hist.push_front(item);
+                } else {
+                    let item = ClipboardItem {
+                        id: format!("{}", std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap()
+                            .as_millis()),
+                        content: content.clone(),
+                        timestamp: std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap()
+                            .as_secs(),
+                        item_type: classified.item_type,
+                        image_path: classified.image_path,
+                        source: kind.as_str().to_string(),
+                        content_hash: classified.content_hash,
+                    };
+
+                    println!("item: {:?}", item);
+                    hist.push_front(item);
+
+                    // 限制历史记录数量
+                    if hist.len() > 100 {
+                        hist.pop_back();
+                    }
+                }
+
+                // 发送事件到前端
+                if let Err(e) = app.emit("clipboard-changed", &content) {
+                    println!("发送事件失败: {}", e);
+                }
+            }
+        }
+        Err(e) => {
+            println!("获取剪贴板内容失败 ({}): {}", kind.as_str(), e);
+        }
+    }
+}
+
 #[tauri::command]
 fn start_clipboard_monitor(state: State<ClipboardState>, app: AppHandle) {
     let history = state.history.clone();
     let last_content = state.last_content.clone();
-    
+    let primary_last_content = state.primary_last_content.clone();
+    let provider_handle = state.provider.clone();
+    let primary_provider = state.primary_provider.clone();
+
     // 注意：由于Tauri 2的clipboard-manager插件API与之前版本不同，
-    // 这里简化实现，使用原来的get_clipboard_content函数进行轮询
+    // 这里简化实现，通过detect_provider()选出的ClipboardProvider轮询
     std::thread::spawn(move || {
         loop {
             std::thread::sleep(std::time::Duration::from_millis(500));
-            
-            match get_clipboard_content() {
-                Ok(content) => {
-                    println!("获取到剪贴板内容: {:?}", content);
-                    // 避免处理包含错误日志的内容，防止循环
-                    if content.contains("execution error") || content.contains("osascript 输出") {
-                        continue;
-                    }
-                    
-                    let mut last = last_content.lock().unwrap();
-                    if *last != content {
-                        *last = content.clone();
-                        
-                        let item = ClipboardItem {
-                            id: format!("{}", std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap()
-                                .as_millis()),
-                            content: content.clone(),
-                            timestamp: std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap()
-                                .as_secs(),
-                            item_type: "text".to_string(),
-                            image_path: None,
-                        };
-                        
-                        let mut hist = history.lock().unwrap();
-
-                        println!("item: {:?}", item);
-                        hist.push_front(item);
-                        
-                        // 限制历史记录数量
-                        if hist.len() > 100 {
-                            hist.pop_back();
-                        }
-                        
-                        // 发送事件到前端
-                        if let Err(e) = app.emit("clipboard-changed", &content) {
-                            println!("发送事件失败: {}", e);
-                        }
-                    }
-                },
-                Err(e) => {
-                    println!("获取剪贴板内容失败: {}", e);
-                }
+
+            // 每次轮询都重新读取当前provider，这样set_clipboard_provider切换后立即生效
+            let provider = provider_handle.lock().unwrap().clone();
+            if provider.supports(ClipboardKind::Clipboard) {
+                poll_kind(ClipboardKind::Clipboard, &provider, &last_content, &history, &app);
+            }
+
+            // 主provider（默认是arboard）通常不支持主选择；如果它自己支持
+            // （比如用户已手动切换到x-clip/x-sel/wayland）就直接用它轮询，
+            // 否则退回到detect_primary_provider()探测出的专用backend
+            let primary = if provider.supports(ClipboardKind::Primary) {
+                Some(provider)
+            } else {
+                primary_provider.clone()
+            };
+            if let Some(primary) = primary {
+                poll_kind(ClipboardKind::Primary, &primary, &primary_last_content, &history, &app);
             }
         }
     });
@@ -106,13 +183,55 @@ fn save_clipboard_history(history: Vec<ClipboardItem>, state: State<ClipboardSta
 }
 
 #[tauri::command]
-fn write_to_clipboard(_app: AppHandle, content: String) -> Result<(), String> {
-    set_clipboard_content(&content)
+fn write_to_clipboard(
+    state: State<ClipboardState>,
+    content: String,
+    kind: Option<String>,
+) -> Result<(), String> {
+    let kind = kind
+        .map(|k| ClipboardKind::from_str(&k))
+        .transpose()?
+        .unwrap_or(ClipboardKind::Clipboard);
+    let provider = state.provider.lock().unwrap().clone();
+    if provider.supports(kind) {
+        return provider.set_contents(content, kind);
+    }
+
+    // 主provider不支持该kind时（通常是arboard遇到primary），退回到专用backend
+    if kind == ClipboardKind::Primary {
+        if let Some(primary) = &state.primary_provider {
+            if primary.supports(kind) {
+                return primary.set_contents(content, kind);
+            }
+        }
+    }
+
+    provider.set_contents(content, kind)
+}
+
+#[tauri::command]
+fn set_custom_clipboard_config(state: State<ClipboardState>, config: CustomProviderConfig) {
+    *state.custom_config.lock().unwrap() = Some(config);
+}
+
+#[tauri::command]
+fn set_clipboard_provider(state: State<ClipboardState>, name: String) -> Result<(), String> {
+    let custom_config = state.custom_config.lock().unwrap().clone();
+    let provider = build_named_provider(&name, state.history.clone(), custom_config)?;
+    *state.provider.lock().unwrap() = Arc::from(provider);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_clipboard_provider_info(state: State<ClipboardState>) -> String {
+    state.provider.lock().unwrap().name().to_string()
 }
 
 #[tauri::command]
 fn copy_image_to_clipboard(_app: AppHandle, image_path: String) -> Result<(), String> {
-    copy_image_from_file(&image_path)
+    let image_data = std::fs::read(&image_path)
+        .map_err(|e| format!("Failed to read image file: {}", e))?;
+    set_image_from_png(&image_data)
 }
 
 #[tauri::command]
@@ -122,30 +241,15 @@ fn copy_base64_image_to_clipboard(_app: AppHandle, base64_content: String) -> Re
     if data_parts.len() < 2 {
         return Err("Invalid base64 image format".to_string());
     }
-    
+
     let base64_data = data_parts[1];
-    
+
     // 解码Base64数据
     let image_data = BASE64_ENGINE.decode(base64_data)
         .map_err(|e| format!("Failed to decode base64: {}", e))?;
-    
-    // 保存到临时文件
-    // 由于未引入uuid crate，使用当前时间戳作为临时文件名的唯一标识
-    let temp_file = format!("/tmp/tauri_clip_{}.png", std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_millis());
-    
-    std::fs::write(&temp_file, &image_data)
-        .map_err(|e| format!("Failed to write temp file: {}", e))?;
-    
-    // 使用已有的复制图片函数
-    let result = copy_image_from_file(&temp_file);
-    
-    // 清理临时文件（忽略错误）
-    let _ = std::fs::remove_file(&temp_file);
-    
-    result
+
+    // 直接设置到剪贴板，无需再落盘到临时文件
+    set_image_from_png(&image_data)
 }
 
 #[tauri::command]
@@ -161,242 +265,6 @@ fn get_image_base64(image_path: String) -> Result<String, String> {
     Ok(format!("data:image/png;base64,{}", base64))
 }
 
-fn copy_image_from_file(image_path: &str) -> Result<(), String> {
-    // 首先检查文件是否存在
-    use std::path::Path;
-    if !Path::new(image_path).exists() {
-        return Err(format!("Image file does not exist: {}", image_path));
-    }
-    
-    #[cfg(target_os = "macos")]
-    {
-        use std::process::Command;
-        
-        let output = Command::new("osascript")
-            .args(&[
-                "-e",
-                &format!("set the clipboard to (read (POSIX file \"{}\") as «class PNGf»)", image_path)
-            ])
-            .output()
-            .map_err(|e| format!("Failed to execute osascript: {}", e))?;
-        
-        // 输出详细的错误信息
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        
-        if output.status.success() {
-            Ok(())
-        } else {
-            Err(format!("Failed to copy image to clipboard: {}. Image path: {}", stderr, image_path))
-        }
-    }
-    
-    #[cfg(target_os = "windows")]
-    {
-        use std::process::Command;
-        
-        let output = Command::new("powershell")
-            .args(&[
-                "-command",
-                &format!("Set-Clipboard -Path '{}'", image_path)
-            ])
-            .output()
-            .map_err(|e| format!("Failed to execute powershell: {}", e))?;
-        
-        // 输出详细的错误信息
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        
-        if output.status.success() {
-            Ok(())
-        } else {
-            Err(format!("Failed to copy image to clipboard: {}. Image path: {}", stderr, image_path))
-        }
-    }
-    
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-    {
-        Err(format!("Unsupported platform. Image path: {}", image_path))
-    }
-}
-
-fn get_clipboard_content() -> Result<String, String> {
-    #[cfg(target_os = "macos")]
-    {
-        use std::process::Command;
-        
-        // 执行pbpaste命令获取剪贴板文本内容
-        let text_output = Command::new("pbpaste")
-            .output()
-            .map_err(|e| format!("Failed to get clipboard: {}", e))?;
-        
-        // 不输出pbpaste的原始输出，避免被捕获回剪贴板导致循环
-        
-        if text_output.status.success() {
-            let text = String::from_utf8_lossy(&text_output.stdout).to_string();
-            if !text.trim().is_empty() {
-                // 检查是否已经是base64图片格式，如果是，直接返回，避免循环处理
-                if text.starts_with("data:image/") && text.contains("base64,") {
-                    println!("检测到已处理的base64图片格式，直接返回");
-                    return Ok(text);
-                }
-                
-                // 避免处理包含错误日志的内容，防止循环
-                if text.contains("execution error") || text.contains("osascript 输出") {
-                    println!("检测到可能是日志内容，跳过处理");
-                    return Ok(String::new());
-                }
-                
-                return Ok(text);
-            }
-        }
-        
-        // 使用try-catch方式尝试获取并转换图片为base64，避免在没有图片时出错
-        let tmp_file = "/tmp/tauri_clip.png";
-
-        let image_check = Command::new("osascript")
-            .args(&[
-                "-e", "try",
-                "-e", &format!("set imageData to the clipboard as «class PNGf»"),
-                "-e", &format!("set theFile to \"{}\"", tmp_file),
-                "-e", "set fd to open for access theFile with write permission",
-                "-e", "write imageData to fd",
-                "-e", "close access fd",
-                "-e", "on error",
-                "-e", "return \"\"",
-                "-e", "end try"
-            ])
-            .output();
-        
-        
-        println!("osascript 输出: {:?}", image_check);
-        // 判断临时文件是否存在且有内容
-        let base64_output = Command::new("sh")
-            .args(&["-c", &format!("[ -s {} ] && base64 -i {} || echo ''", tmp_file, tmp_file)])
-            .output()
-            .map_err(|e| e.to_string())?;
-        println!("osascript base64_output 输出: {:?}", base64_output);
-
-        let base64_str = String::from_utf8_lossy(&base64_output.stdout).trim().to_string();
-        println!("osascript base64_str 输出: {:?}", base64_str);
-
-        if !base64_str.is_empty() {
-            println!("检测到图片数据，已转换为base64");
-            return Ok(format!("data:image/png;base64,{}", base64_str));
-        }
-        
-        Ok(String::new())
-    }
-    
-    #[cfg(target_os = "windows")]
-    {
-        use std::process::Command;
-        
-        // 尝试获取文本内容
-        let text_output = Command::new("powershell")
-            .args(&["-command", "Get-Clipboard -Format Text"])
-            .output()
-            .map_err(|e| format!("Failed to get clipboard: {}", e))?;
-        
-        if text_output.status.success() {
-            let text = String::from_utf8_lossy(&text_output.stdout).to_string();
-            if !text.trim().is_empty() {
-                // 检查是否已经是base64图片格式，如果是，直接返回，避免循环处理
-                if text.starts_with("data:image/") && text.contains("base64,") {
-                    println!("检测到已处理的base64图片格式，直接返回");
-                    return Ok(text);
-                }
-                return Ok(text);
-            }
-        }
-        
-        // 检查是否有图片并直接转换为base64
-        let image_script = r#"
-        $tempPath = [System.IO.Path]::GetTempFileName() + '.png'
-        try {
-            # 尝试获取剪贴板中的图片
-            $image = Get-Clipboard -Format Image
-            if ($image -ne $null) {
-                # 保存到临时文件
-                $image.Save($tempPath, [System.Drawing.Imaging.ImageFormat]::Png)
-                # 读取文件并转换为base64
-                $bytes = [System.IO.File]::ReadAllBytes($tempPath)
-                $base64 = [System.Convert]::ToBase64String($bytes)
-                Write-Output $base64
-            }
-        } catch {
-            # 忽略错误
-        } finally {
-            # 清理临时文件
-            if (Test-Path $tempPath) {
-                Remove-Item $tempPath -Force
-            }
-        }
-        "#;
-        
-        let image_output = Command::new("powershell")
-            .args(&["-command", &image_script])
-            .output();
-        
-        if let Ok(output) = image_output {
-            if output.status.success() {
-                let base64 = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                if !base64.is_empty() {
-                    println!("检测到图片数据，已转换为base64");
-                    return Ok(format!("data:image/png;base64,{}", base64));
-                }
-            }
-        }
-        
-        Ok(String::new())
-    }
-    
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-    {
-        Err("Unsupported platform".to_string())
-    }
-}
-
-fn set_clipboard_content(content: &str) -> Result<(), String> {
-    #[cfg(target_os = "macos")]
-    {
-        use std::process::Command;
-        let mut child = Command::new("pbcopy")
-            .stdin(std::process::Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("Failed to set clipboard: {}", e))?;
-        
-        if let Some(stdin) = child.stdin.as_mut() {
-            use std::io::Write;
-            stdin.write_all(content.as_bytes())
-                .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
-        }
-        
-        child.wait()
-            .map_err(|e| format!("Failed to wait for pbcopy: {}", e))?;
-        
-        Ok(())
-    }
-    
-    #[cfg(target_os = "windows")]
-    {
-        use std::process::Command;
-        let output = Command::new("powershell")
-            .args(&["-command", &format!("Set-Clipboard -Value '{}'", content)])
-            .output()
-            .map_err(|e| format!("Failed to set clipboard: {}", e))?;
-        
-        if output.status.success() {
-            Ok(())
-        } else {
-            Err("Failed to set clipboard content ".to_string())
-        }
-    }
-    
-    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-    {
-        Err("Unsupported platform".to_string())
-    }
-}
-
 #[tauri::command]
 fn toggle_always_on_top(window: Window) -> Result<(), String> {
     let is_always_on_top = window.is_always_on_top().map_err(|e| e.to_string())?;
@@ -484,6 +352,9 @@ pub fn run() {
             copy_image_to_clipboard,
             copy_base64_image_to_clipboard,
             get_image_base64,
+            set_custom_clipboard_config,
+            set_clipboard_provider,
+            get_clipboard_provider_info,
             toggle_always_on_top,
             minimize_to_tray,
             show_window